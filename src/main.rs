@@ -1,8 +1,11 @@
 #![windows_subsystem = "windows"]
 
 use rand::{Rng, thread_rng};
+use rand::seq::SliceRandom;
 use raylib::prelude::*;
 use std::fmt::{Display, Error, Formatter};
+use std::fs;
+use std::path::PathBuf;
 
 #[derive(Clone, Copy)]
 struct Cell {
@@ -54,7 +57,105 @@ impl Display for Cell {
 enum State {
     Playing,
     GameOwover,
-    _Victory,
+    Victory,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Dir {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Dir {
+    const ALL: [Dir; 4] = [Dir::Left, Dir::Right, Dir::Up, Dir::Down];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Dir::Left => "LEFT",
+            Dir::Right => "RIGHT",
+            Dir::Up => "UP",
+            Dir::Down => "DOWN",
+        }
+    }
+}
+
+// Quadratic ease-out: starts fast, settles into place.
+fn interp_sq_inv(x: f32) -> f32 {
+    let x = x.clamp(0.0, 1.0);
+    -(x - 1.0).powi(2) + 1.0
+}
+
+// Quadratic ease-in: starts slow, accelerates.
+fn interp_sq(x: f32) -> f32 {
+    let x = x.clamp(0.0, 1.0);
+    x * x
+}
+
+// Blends ease-out into ease-in for a gentle slide.
+fn ease_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        interp_sq_inv(t * 2.0) / 2.0
+    } else {
+        interp_sq((t - 0.5) * 2.0) / 2.0 + 0.5
+    }
+}
+
+// Peaks at t=0.5 — the merged-tile overshoot bump.
+fn bump_scale(t: f32) -> f32 {
+    if t < 0.5 {
+        interp_sq_inv(t * 2.0)
+    } else {
+        1.0 - interp_sq((t - 0.5) * 2.0)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum AnimKind {
+    Move,
+    Merge,
+    Spawn,
+}
+
+// Keyed by destination cell; `from == to` for a spawn.
+#[derive(Clone, Copy)]
+struct TileAnim {
+    from: (usize, usize),
+    to: (usize, usize),
+    kind: AnimKind,
+    timer: f32,
+}
+
+impl TileAnim {
+    fn from_move(mv: TileMove) -> Self {
+        let kind = if mv.merged { AnimKind::Merge } else { AnimKind::Move };
+        let mut anim = TileAnim { from: mv.from, to: mv.to, kind, timer: 0.0 };
+        anim.timer = anim.duration();
+        anim
+    }
+
+    fn spawn(at: (usize, usize)) -> Self {
+        TileAnim { from: at, to: at, kind: AnimKind::Spawn, timer: ANIMATION_DURATION }
+    }
+
+    fn duration(&self) -> f32 {
+        match self.kind {
+            AnimKind::Merge => ANIMATION_DURATION + MERGE_BUMP_DURATION,
+            _ => ANIMATION_DURATION,
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        self.timer > 0.0
+    }
+}
+
+#[derive(Clone, Copy)]
+struct TileMove {
+    from: (usize, usize),
+    to: (usize, usize),
+    merged: bool,
 }
 
 #[derive(Copy, Clone)]
@@ -67,20 +168,27 @@ struct Particle {
     vel_y: f32,
     color: Color,
     life: f32,
+    max_life: f32,
+    friction: f32,
+    outline: bool,
 }
 
 impl Particle {
-    fn rand(x: f32, y: f32, color: Color) -> Self {
-        let size = thread_rng().gen_range(5..10);
+    fn new(preset: ParticlePreset, x: f32, y: f32, color: Color) -> Self {
+        let size = thread_rng().gen_range(preset.size.0..preset.size.1);
+        let life = thread_rng().gen_range(preset.life.0..preset.life.1);
         Particle {
             x,
             y,
             width: size,
             height: size,
-            vel_x: thread_rng().gen_range(-50.0..50.0),
-            vel_y: thread_rng().gen_range(-50.0..50.0),
+            vel_x: thread_rng().gen_range(-preset.velocity..preset.velocity),
+            vel_y: thread_rng().gen_range(-preset.velocity..preset.velocity),
             color,
-            life: thread_rng().gen_range(150.0..250.0),
+            life,
+            max_life: life,
+            friction: preset.friction,
+            outline: preset.outline,
         }
     }
 
@@ -88,24 +196,27 @@ impl Particle {
         self.x += self.vel_x * dt;
         self.y += self.vel_y * dt;
         self.life -= dt * PARTICLE_LIFE_DECAY;
-        self.vel_x = decrease_abs(self.vel_x, PARTICLE_FRICTION * dt);
-        self.vel_y = decrease_abs(self.vel_y, PARTICLE_FRICTION * dt);
+        self.vel_x = decrease_abs(self.vel_x, self.friction * dt);
+        self.vel_y = decrease_abs(self.vel_y, self.friction * dt);
     }
 
     fn render(&self, d: &mut RaylibDrawHandle) {
         if self.life > 0.0 {
+            let alpha = ((self.life / self.max_life) * 255.0) as u8;
             d.draw_rectangle(
-                self.x as i32, 
-                self.y as i32, 
-                self.width as i32, 
-                self.height as i32, 
-                Color::new(self.color.r, self.color.g, self.color.b, ((self.life / PARTICLE_LIFE) * 255.0) as u8));
-            d.draw_rectangle_lines(
-                self.x as i32, 
-                self.y as i32, 
-                self.width as i32, 
-                self.height as i32, 
-                Color::new(0xff, 0xff, 0xff, ((self.life / PARTICLE_LIFE) * 255.0) as u8));
+                self.x as i32,
+                self.y as i32,
+                self.width as i32,
+                self.height as i32,
+                Color::new(self.color.r, self.color.g, self.color.b, alpha));
+            if self.outline {
+                d.draw_rectangle_lines(
+                    self.x as i32,
+                    self.y as i32,
+                    self.width as i32,
+                    self.height as i32,
+                    Color::new(0xff, 0xff, 0xff, alpha));
+            }
         }
     }
 
@@ -118,11 +229,191 @@ impl Particle {
     }
 }
 
+// A named burst preset: how many particles to spawn and the ranges their
+// size/velocity/life are drawn from, modeled as a small per-effect data table
+// rather than hardcoding a single burst shape at every emit site.
+#[derive(Clone, Copy)]
+struct ParticlePreset {
+    count: (u32, u32),
+    size: (u32, u32),
+    velocity: f32,
+    life: (f32, f32),
+    friction: f32,
+    outline: bool,
+}
+
+const PRESET_PUFF: ParticlePreset = ParticlePreset {
+    count: (10, 16),
+    size: (4, 8),
+    velocity: 35.0,
+    life: (120.0, 200.0),
+    friction: 20.0,
+    outline: true,
+};
+
+const PRESET_BURST: ParticlePreset = ParticlePreset {
+    count: (18, 26),
+    size: (5, 10),
+    velocity: 50.0,
+    life: (150.0, 250.0),
+    friction: 20.0,
+    outline: true,
+};
+
+const PRESET_CELEBRATION: ParticlePreset = ParticlePreset {
+    count: (50, 70),
+    size: (8, 14),
+    velocity: 120.0,
+    life: (250.0, 400.0),
+    friction: 15.0,
+    outline: true,
+};
+
+// Picks the burst preset for the tile value a merge just produced: small
+// merges get a modest puff, reaching 2048 triggers a big celebratory burst.
+fn preset_for_value(value: u32) -> ParticlePreset {
+    if value >= MAX_SCORE {
+        PRESET_CELEBRATION
+    } else if value >= PRESET_BURST_THRESHOLD {
+        PRESET_BURST
+    } else {
+        PRESET_PUFF
+    }
+}
+
+fn spawn(preset: ParticlePreset, x: f32, y: f32, color: Color) -> Vec<Particle> {
+    let count = thread_rng().gen_range(preset.count.0..=preset.count.1);
+    (0..count).map(|_| Particle::new(preset, x, y, color)).collect()
+}
+
+// All-time stats, persisted next to the executable and reloaded at startup.
+struct SaveData {
+    best_score: u32,
+    best_tile: u32,
+    games_played: u32,
+    unlocked: Vec<String>,
+}
+
+impl SaveData {
+    fn empty() -> Self {
+        SaveData {
+            best_score: 0,
+            best_tile: 0,
+            games_played: 0,
+            unlocked: Vec::new(),
+        }
+    }
+
+    fn load() -> Self {
+        let Ok(contents) = fs::read_to_string(save_path()) else {
+            return Self::empty();
+        };
+        let mut data = Self::empty();
+        for line in contents.lines() {
+            if let Some((key, value)) = line.split_once('=') {
+                match key {
+                    "best_score" => data.best_score = value.parse().unwrap_or(0),
+                    "best_tile" => data.best_tile = value.parse().unwrap_or(0),
+                    "games_played" => data.games_played = value.parse().unwrap_or(0),
+                    "achievement" => data.unlocked.push(value.to_string()),
+                    _ => {}
+                }
+            }
+        }
+        data
+    }
+
+    fn save(&self) {
+        let mut contents = format!(
+            "best_score={}\nbest_tile={}\ngames_played={}\n",
+            self.best_score, self.best_tile, self.games_played
+        );
+        for id in &self.unlocked {
+            contents.push_str(&format!("achievement={}\n", id));
+        }
+        let _ = fs::write(save_path(), contents);
+    }
+
+    fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.iter().any(|unlocked_id| unlocked_id == id)
+    }
+
+    // Folds the result of a finished game into the all-time stats.
+    fn record_game(&mut self, score: u32, best_tile: u32) {
+        self.best_score = self.best_score.max(score);
+        self.best_tile = self.best_tile.max(best_tile);
+        self.games_played += 1;
+    }
+}
+
+fn save_path() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(SAVE_FILE_NAME)))
+        .unwrap_or_else(|| PathBuf::from(SAVE_FILE_NAME))
+}
+
+struct Achievement {
+    id: &'static str,
+    name: &'static str,
+    check: fn(&GameState) -> bool,
+}
+
+const ACHIEVEMENTS: [Achievement; 4] = [
+    Achievement { id: "reach_256", name: "Reach 256", check: |gs| has_tile(gs, 256) },
+    Achievement { id: "reach_2048", name: "Reach 2048", check: |gs| has_tile(gs, MAX_SCORE) },
+    Achievement {
+        id: "win_without_undo",
+        name: "Win without undo",
+        check: |gs| gs.state == State::Victory && !gs.used_undo,
+    },
+    Achievement {
+        id: "clear_6_merges",
+        name: "Clear 6 merges in one move",
+        check: |gs| gs.last_merge_count >= 6,
+    },
+];
+
+fn has_tile(gs: &GameState, value: u32) -> bool {
+    gs.cells.iter().flatten().any(|cell| cell.value >= value)
+}
+
+// Checks every not-yet-unlocked achievement and queues a toast for each one
+// that fires. Unlocks are flushed to disk alongside the rest of `SaveData`.
+fn check_achievements(gs: &mut GameState) {
+    for achievement in ACHIEVEMENTS {
+        if !gs.save.is_unlocked(achievement.id) && (achievement.check)(gs) {
+            gs.save.unlocked.push(achievement.id.to_string());
+            gs.toasts.push(Toast {
+                text: format!("Achievement: {}", achievement.name),
+                life: TOAST_LIFE,
+            });
+        }
+    }
+}
+
+// A brief on-screen notification, rendered with the same text/particle
+// facilities as the rest of the HUD and ticked down like a particle's life.
+struct Toast {
+    text: String,
+    life: f32,
+}
+
 struct GameState {
     cells: [[Cell; CELL_DIM]; CELL_DIM],
     score: u32,
     state: State,
     particles: Vec<Particle>,
+    ai_enabled: bool,
+    ai_move_timer: f32,
+    hint: Option<Dir>,
+    history: Vec<([[Cell; CELL_DIM]; CELL_DIM], u32)>,
+    save: SaveData,
+    used_undo: bool,
+    last_merge_count: u32,
+    toasts: Vec<Toast>,
+    target_score: Option<u32>,
+    animations: Vec<TileAnim>,
 }
 
 impl GameState {
@@ -132,14 +423,71 @@ impl GameState {
             score: 0,
             state: State::Playing,
             particles: Vec::new(),
+            ai_enabled: false,
+            ai_move_timer: 0.0,
+            hint: None,
+            history: Vec::new(),
+            save: SaveData::load(),
+            used_undo: false,
+            last_merge_count: 0,
+            toasts: Vec::new(),
+            target_score: None,
+            animations: Vec::new(),
         }
     }
 
+    // Resets to a fresh random game, clearing any puzzle-mode target score.
     fn reset(&mut self) {
         self.cells = random_cells();
         self.score = 0;
         self.state = State::Playing;
         self.particles = Vec::new();
+        self.ai_move_timer = 0.0;
+        self.hint = None;
+        self.history.clear();
+        self.used_undo = false;
+        self.last_merge_count = 0;
+        self.toasts.clear();
+        self.target_score = None;
+        self.animations.clear();
+    }
+
+    // Loads a puzzle-mode starting layout in place of a random board.
+    fn load_puzzle(&mut self, cells: [[Cell; CELL_DIM]; CELL_DIM], target_score: Option<u32>) {
+        self.cells = cells;
+        self.score = 0;
+        self.state = State::Playing;
+        self.particles = Vec::new();
+        self.ai_move_timer = 0.0;
+        self.hint = None;
+        self.history.clear();
+        self.used_undo = false;
+        self.last_merge_count = 0;
+        self.toasts.clear();
+        self.target_score = target_score;
+        self.animations.clear();
+    }
+
+    // Snapshots the current board/score so `undo` can restore them later.
+    fn push_history(&mut self) {
+        self.history.push((self.cells, self.score));
+        if self.history.len() > UNDO_DEPTH {
+            self.history.remove(0);
+        }
+    }
+
+    fn undo(&mut self) -> bool {
+        if let Some((cells, score)) = self.history.pop() {
+            self.cells = cells;
+            self.score = score;
+            self.state = State::Playing;
+            self.hint = None;
+            self.used_undo = true;
+            self.animations.clear();
+            true
+        } else {
+            false
+        }
     }
 }
 
@@ -152,23 +500,45 @@ const MAX_SCORE: u32 = 2048;
 const COLORS: u32 = MAX_SCORE.ilog2() + 2;
 const WIDTH: i32 = 500;
 const HEIGHT: i32 = WIDTH;
-const PARTICLE_LIFE: f32 = 200.0;
 const PARTICLE_LIFE_DECAY: f32 = 200.0;
-const PARTICLE_FRICTION: f32 = 20.0;
+const AI_SEARCH_DEPTH: u32 = 3;
+const AI_CHANCE_SAMPLE_LIMIT: usize = 4;
+const AI_MOVE_INTERVAL: f32 = 0.15;
+const EVAL_EMPTY_WEIGHT: f32 = 2.7;
+const EVAL_SMOOTHNESS_WEIGHT: f32 = 0.1;
+const EVAL_MONOTONICITY_WEIGHT: f32 = 1.0;
+const EVAL_CORNER_WEIGHT: f32 = 2.0;
+const UNDO_DEPTH: usize = 16;
+const PRESET_BURST_THRESHOLD: u32 = 64;
+const SAVE_FILE_NAME: &str = "2048_save.txt";
+const TOAST_LIFE: f32 = 2.5;
+const TOAST_LIFE_DECAY: f32 = 1.0;
+const ANIMATION_DURATION: f32 = 0.12;
+const MERGE_BUMP_DURATION: f32 = 0.08;
+const MERGE_BUMP_SCALE: f32 = 0.2;
 
 fn main() {
     let (mut rl, thread) = raylib::init()
     .size(WIDTH, HEIGHT)
     .title("Hello, World")
         .build();
-    
+
     let board = Rectangle { x: 50.0, y: 50.0, width: BOARD_SIZE, height: BOARD_SIZE };
     // let mut cells = [[Cell::empty(); CELL_DIM]; CELL_DIM];
     // let mut score = 0;
     // let mut state = State::Playing;
     // let mut particles = Vec::new();
     let mut gs = GameState::new();
-    gs.reset();
+
+    let args: Vec<String> = std::env::args().collect();
+    match puzzle_path_arg(&args).map(load_board) {
+        Some(Ok((cells, target_score))) => gs.load_puzzle(cells, target_score),
+        Some(Err(err)) => {
+            eprintln!("Falling back to a random board: {}", err);
+            gs.reset();
+        }
+        None => gs.reset(),
+    }
 
     while !rl.window_should_close() {
         // Reset
@@ -176,30 +546,74 @@ fn main() {
             gs.reset();
         }
 
+        // Undo
+        if rl.is_key_pressed(KeyboardKey::KEY_Z) {
+            gs.undo();
+        }
+
         if gs.state == State::Playing {
+            // AI toggle / hint
+            if rl.is_key_pressed(KeyboardKey::KEY_A) {
+                gs.ai_enabled = !gs.ai_enabled;
+                gs.ai_move_timer = 0.0;
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_H) {
+                gs.hint = best_move(gs.cells);
+            }
+
             let mut moved = false;
             // Movement
+            let mut dir = None;
             if rl.is_key_released(KeyboardKey::KEY_RIGHT) {
-                moved = true;
-                gs.score += slide_right(&mut gs.cells, &mut gs.particles, board);
+                dir = Some(Dir::Right);
             } else if rl.is_key_released(KeyboardKey::KEY_LEFT) {
-                moved = true;
-                gs.score += slide_left(&mut gs.cells, &mut gs.particles, board);
+                dir = Some(Dir::Left);
             } else if rl.is_key_released(KeyboardKey::KEY_DOWN) {
-                moved = true;
-                gs.score += slide_down(&mut gs.cells, &mut gs.particles, board);
+                dir = Some(Dir::Down);
             } else if rl.is_key_released(KeyboardKey::KEY_UP) {
-                moved = true;
-                gs.score += slide_up(&mut gs.cells, &mut gs.particles, board);
+                dir = Some(Dir::Up);
+            }
+
+            // Gate movement while the previous move's slide is still
+            // animating. A keypress during the animation fast-forwards it
+            // (snaps everything to rest) instead of queuing another move.
+            if !gs.animations.is_empty() {
+                if dir.is_some() {
+                    gs.animations.clear();
+                }
+                dir = None;
+            }
+
+            if gs.ai_enabled && dir.is_none() && gs.animations.is_empty() {
+                gs.ai_move_timer -= rl.get_frame_time();
+                if gs.ai_move_timer <= 0.0 {
+                    dir = best_move(gs.cells);
+                    gs.ai_move_timer = AI_MOVE_INTERVAL;
+                }
             }
-    
+
+            if let Some(dir) = dir {
+                // Check first whether this direction would actually change
+                // the board, so a move that can't do anything doesn't burn
+                // an undo slot or spawn a free tile.
+                let (_, _, would_move) = try_slide(gs.cells, dir);
+                if would_move {
+                    moved = true;
+                    gs.hint = None;
+                    gs.push_history();
+                    let (gained, _) = slide(&mut gs.cells, dir, &mut gs.particles, &mut gs.animations, board);
+                    gs.score += gained;
+                }
+            }
+
+            gs.last_merge_count = gs.cells.iter().flatten().filter(|cell| cell.combined).count() as u32;
             for y in 0..gs.cells.len() {
                 for x in 0..gs.cells[y].len() {
                     gs.cells[y][x].combined = false;
                 }
             }
-            
-            if moved { 
+
+            if moved {
                 let mut has_empty_cell = false;
                 for y in 0..gs.cells.len() {
                     for x in 0..gs.cells[y].len() {
@@ -208,17 +622,31 @@ fn main() {
                 }
                 if has_empty_cell {
                     loop {
-                        // TODO: Shouldn't generate a new random cell when sliding doesn't move any cells
                         let x = thread_rng().gen_range(0..CELL_DIM);
                         let y = thread_rng().gen_range(0..CELL_DIM);
                         if gs.cells[y][x].is_empty() {
                            gs.cells[y][x] = Cell::occupied(2_i32.pow(thread_rng().gen::<u32>() % 2 + 1) as u32);
+                           gs.animations.push(TileAnim::spawn((x, y)));
                            break;
                         }
                     }
                 } else {
                     gs.state = State::GameOwover;
                 }
+
+                if let Some(target) = gs.target_score {
+                    if gs.score >= target {
+                        gs.state = State::Victory;
+                    }
+                }
+
+                check_achievements(&mut gs);
+
+                if gs.state == State::GameOwover || gs.state == State::Victory {
+                    let best_tile = gs.cells.iter().flatten().map(|cell| cell.value).max().unwrap_or(0);
+                    gs.save.record_game(gs.score, best_tile);
+                    gs.save.save();
+                }
             }
         }
 
@@ -226,6 +654,17 @@ fn main() {
             particle.tick(rl.get_frame_time());
         }
 
+        for anim in gs.animations.iter_mut() {
+            anim.timer -= rl.get_frame_time();
+        }
+        gs.animations.retain(|anim| anim.is_alive());
+
+        let dt = rl.get_frame_time();
+        for toast in gs.toasts.iter_mut() {
+            toast.life -= dt * TOAST_LIFE_DECAY;
+        }
+        gs.toasts.retain(|toast| toast.life > 0.0);
+
         let mut d = rl.begin_drawing(&thread);
         d.clear_background(Color::from_hex("181818").unwrap());
         let text_size = d.get_font_default().measure_text(format!("{}", gs.score).as_str(), 30.0, 2.0);
@@ -233,10 +672,49 @@ fn main() {
             format!("{}", gs.score).as_str(), 
             (WIDTH as f32 / 2.0 - text_size.x / 2.0) as i32,
             (10.0) as i32,
-            30, 
+            30,
             Color::BEIGE
         );
-        draw_board(&mut d, gs.cells, board);
+        d.draw_text(
+            format!("Best: {}", gs.save.best_score).as_str(),
+            10,
+            10,
+            18,
+            Color::BEIGE,
+        );
+        if let Some(target) = gs.target_score {
+            d.draw_text(
+                format!("Target: {}", target).as_str(),
+                WIDTH - 120,
+                10,
+                18,
+                Color::BEIGE,
+            );
+        }
+        draw_board(&mut d, gs.cells, board, &gs.animations);
+
+        if gs.ai_enabled {
+            d.draw_text("AI: ON (A to toggle)", 10, (HEIGHT - 25) as i32, 18, Color::BEIGE);
+        } else if let Some(hint) = gs.hint {
+            d.draw_text(
+                format!("Hint: {}", hint.label()).as_str(),
+                10,
+                (HEIGHT - 25) as i32,
+                18,
+                Color::BEIGE,
+            );
+        }
+
+        for (i, toast) in gs.toasts.iter().enumerate() {
+            let alpha = ((toast.life / TOAST_LIFE).min(1.0) * 255.0) as u8;
+            d.draw_text(
+                toast.text.as_str(),
+                10,
+                45 + i as i32 * 22,
+                20,
+                Color::new(0xff, 0xd7, 0x00, alpha),
+            );
+        }
 
         if gs.state == State::GameOwover {
             d.draw_rectangle_rounded(board, 0.05, 10, Color::new(64, 64, 128, 196));
@@ -245,57 +723,59 @@ fn main() {
                 "Game OwOver",
                 (board.x + board.width / 2.0 - text_size.x / 2.0) as i32, 
                 (board.y + board.height / 2.0 - text_size.y / 2.0) as i32, 
-                50, 
+                50,
                 Color::BEIGE
-            );       
+            );
         }
-        
+
+        if gs.state == State::Victory {
+            d.draw_rectangle_rounded(board, 0.05, 10, Color::new(64, 128, 64, 196));
+            let text_size = d.get_font_default().measure_text("Target Reached!", 50.0, 2.0);
+            d.draw_text(
+                "Target Reached!",
+                (board.x + board.width / 2.0 - text_size.x / 2.0) as i32,
+                (board.y + board.height / 2.0 - text_size.y / 2.0) as i32,
+                50,
+                Color::BEIGE
+            );
+        }
+
         for particle in gs.particles.iter() {
             particle.render(&mut d);
         }
         gs.particles.retain(|p| p.is_alive()); // Remove any particles which have 'died'
     }
+
+    gs.save.save();
 }
 
-fn draw_board(d: &mut RaylibDrawHandle, cells: [[Cell; CELL_DIM]; CELL_DIM], board: Rectangle) {
+fn draw_board(d: &mut RaylibDrawHandle, cells: [[Cell; CELL_DIM]; CELL_DIM], board: Rectangle, animations: &[TileAnim]) {
     d.draw_rectangle_rounded_lines(board, 0.05, 10, 2.0, Color::BEIGE);
     for y in 0..cells.len() {
         for x in 0..cells[0].len() {
-            let cell_x = board.x + CELL_PAD * (x as f32 + 1.0) + x as f32 * CELL_SIZE;
-            let cell_y = board.y + CELL_PAD * (y as f32 + 1.0) + y as f32 * CELL_SIZE;
+            let (cell_x, cell_y) = cell_pixel_pos(board, x, y);
             let cell = cells[y][x];
-            if !cell.is_empty() {
-                d.draw_rectangle_rounded(
-                    Rectangle { 
-                        x: cell_x, 
-                        y: cell_y, 
-                        width: CELL_SIZE, 
-                        height: CELL_SIZE 
-                    }, 
-                    0.1,
-                    10,
-                    get_cell_color(cell.value),
-                );
-            }
             d.draw_rectangle_rounded_lines(
-                Rectangle { 
-                    x: cell_x, 
-                    y: cell_y, 
-                    width: CELL_SIZE, 
-                    height: CELL_SIZE 
-                }, 
+                Rectangle {
+                    x: cell_x,
+                    y: cell_y,
+                    width: CELL_SIZE,
+                    height: CELL_SIZE
+                },
                 0.1,
                 10,
                 2.0,
                 Color::BEIGE
             );
             if !cell.is_empty() {
+                let tile_rect = animated_tile_rect(board, x, y, animations);
+                d.draw_rectangle_rounded(tile_rect, 0.1, 10, get_cell_color(cell.value));
                 let text_size = d.get_font_default().measure_text(format!("{}", cell.value).as_str(), 30.0, 2.0);
                 d.draw_text(
-                    format!("{}", cell.value).as_str(), 
-                    (cell_x + CELL_SIZE / 2.0 - text_size.x / 2.0) as i32,
-                    (cell_y + CELL_SIZE / 2.0 - text_size.y / 2.0) as i32,
-                    30, 
+                    format!("{}", cell.value).as_str(),
+                    (tile_rect.x + tile_rect.width / 2.0 - text_size.x / 2.0) as i32,
+                    (tile_rect.y + tile_rect.height / 2.0 - text_size.y / 2.0) as i32,
+                    30,
                     Color::BEIGE
                 );
             }
@@ -303,6 +783,54 @@ fn draw_board(d: &mut RaylibDrawHandle, cells: [[Cell; CELL_DIM]; CELL_DIM], boa
     }
 }
 
+// Resting rect for (x, y), or its animated position/scale if one targets it.
+fn animated_tile_rect(board: Rectangle, x: usize, y: usize, animations: &[TileAnim]) -> Rectangle {
+    let (rest_x, rest_y) = cell_pixel_pos(board, x, y);
+    let rest = Rectangle { x: rest_x, y: rest_y, width: CELL_SIZE, height: CELL_SIZE };
+
+    let anim = match animations.iter().find(|a| a.to == (x, y)) {
+        Some(anim) => anim,
+        None => return rest,
+    };
+
+    let scale = match anim.kind {
+        AnimKind::Spawn => {
+            let t = 1.0 - (anim.timer / anim.duration()).clamp(0.0, 1.0);
+            interp_sq(t)
+        }
+        AnimKind::Merge if anim.timer <= MERGE_BUMP_DURATION => {
+            let t = 1.0 - (anim.timer / MERGE_BUMP_DURATION).clamp(0.0, 1.0);
+            1.0 + MERGE_BUMP_SCALE * bump_scale(t)
+        }
+        _ => 1.0,
+    };
+
+    let (pos_x, pos_y) = match anim.kind {
+        AnimKind::Spawn => (rest_x, rest_y),
+        AnimKind::Merge if anim.timer <= MERGE_BUMP_DURATION => (rest_x, rest_y),
+        AnimKind::Move | AnimKind::Merge => {
+            let slide_timer = match anim.kind {
+                AnimKind::Merge => anim.timer - MERGE_BUMP_DURATION,
+                _ => anim.timer,
+            };
+            let t = 1.0 - (slide_timer / ANIMATION_DURATION).clamp(0.0, 1.0);
+            let eased = ease_in_out(t);
+            let (from_x, from_y) = cell_pixel_pos(board, anim.from.0, anim.from.1);
+            (
+                from_x + (rest_x - from_x) * eased,
+                from_y + (rest_y - from_y) * eased,
+            )
+        }
+    };
+
+    Rectangle {
+        x: pos_x + CELL_SIZE * (1.0 - scale) / 2.0,
+        y: pos_y + CELL_SIZE * (1.0 - scale) / 2.0,
+        width: CELL_SIZE * scale,
+        height: CELL_SIZE * scale,
+    }
+}
+
 fn get_cell_color(cell_value: u32) -> Color {
     let mut hsv = CELL_BASE_COLOR.color_to_hsv();
     hsv.z = 1.0 - (hsv.z / COLORS as f32 * cell_value.ilog2() as f32);
@@ -325,7 +853,129 @@ fn random_cells() -> [[Cell; CELL_DIM]; CELL_DIM] {
     cells
 }
 
-fn slide_right(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], particles: &mut Vec<Particle>, board: Rectangle) -> u32 {
+#[derive(Debug)]
+enum LoadError {
+    Io(String),
+    UnexpectedEof,
+    WrongDimensions,
+    TooManyTiles,
+    BadChar(String),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            LoadError::Io(message) => write!(f, "could not read puzzle file: {}", message),
+            LoadError::UnexpectedEof => write!(f, "puzzle file ended before {} rows were read", CELL_DIM),
+            LoadError::WrongDimensions => write!(f, "each board row must have exactly {} tokens", CELL_DIM),
+            LoadError::TooManyTiles => write!(f, "a board row had more than {} tokens", CELL_DIM),
+            LoadError::BadChar(token) => write!(f, "expected `.` or a power-of-two tile, found `{}`", token),
+        }
+    }
+}
+
+// 4 lines of 4 whitespace-separated tokens, with an optional `target <score>` header.
+fn load_board(path: &str) -> Result<([[Cell; CELL_DIM]; CELL_DIM], Option<u32>), LoadError> {
+    let contents = fs::read_to_string(path).map_err(|err| LoadError::Io(err.to_string()))?;
+    let mut lines = contents.lines().map(str::trim).filter(|line| !line.is_empty());
+
+    let mut first = lines.next().ok_or(LoadError::UnexpectedEof)?;
+    let mut target_score = None;
+    if let Some(rest) = first.strip_prefix("target") {
+        let rest = rest.trim();
+        target_score = Some(rest.parse().map_err(|_| LoadError::BadChar(rest.to_string()))?);
+        first = lines.next().ok_or(LoadError::UnexpectedEof)?;
+    }
+
+    let mut row_lines = Vec::with_capacity(CELL_DIM);
+    row_lines.push(first);
+    row_lines.extend(lines.by_ref().take(CELL_DIM - 1));
+    if row_lines.len() < CELL_DIM {
+        return Err(LoadError::UnexpectedEof);
+    }
+
+    let mut cells = [[Cell::empty(); CELL_DIM]; CELL_DIM];
+    for (y, line) in row_lines.iter().enumerate() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() > CELL_DIM {
+            return Err(LoadError::TooManyTiles);
+        }
+        if tokens.len() != CELL_DIM {
+            return Err(LoadError::WrongDimensions);
+        }
+        for (x, token) in tokens.iter().enumerate() {
+            if *token == "." {
+                continue;
+            }
+            let value: u32 = token.parse().map_err(|_| LoadError::BadChar(token.to_string()))?;
+            if value < 2 || !value.is_power_of_two() {
+                return Err(LoadError::BadChar(token.to_string()));
+            }
+            cells[y][x] = Cell::occupied(value);
+        }
+    }
+
+    Ok((cells, target_score))
+}
+
+// Reads `--puzzle <path>` off the command line, if present.
+fn puzzle_path_arg(args: &[String]) -> Option<&str> {
+    args.iter()
+        .position(|arg| arg == "--puzzle")
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+// Gameplay entry point, built on the same `merge` pass as `try_slide`.
+fn slide(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], dir: Dir, particles: &mut Vec<Particle>, animations: &mut Vec<TileAnim>, board: Rectangle) -> (u32, bool) {
+    let before = *cells;
+    let mut moves = Vec::new();
+    let score = merge(cells, dir, Some(&mut moves));
+    emit_merge_particles(cells, particles, board);
+    animations.extend(moves.into_iter().map(TileAnim::from_move));
+    (score, *cells != before)
+}
+
+// Emits a merge burst at every cell the last `merge` pass marked as `combined`.
+fn emit_merge_particles(cells: &[[Cell; CELL_DIM]; CELL_DIM], particles: &mut Vec<Particle>, board: Rectangle) {
+    for y in 0..cells.len() {
+        for x in 0..cells[y].len() {
+            if cells[y][x].combined {
+                let (cell_px_x, cell_px_y) = cell_pixel_pos(board, x, y);
+                let preset = preset_for_value(cells[y][x].value);
+                particles.append(&mut spawn(preset, cell_px_x + CELL_SIZE / 2.0, cell_px_y + CELL_SIZE / 2.0, get_cell_color(cells[y][x].value)));
+            }
+        }
+    }
+}
+
+// Top-left pixel corner of grid cell `(x, y)` within `board`.
+fn cell_pixel_pos(board: Rectangle, x: usize, y: usize) -> (f32, f32) {
+    (
+        board.x + CELL_PAD * (x as f32 + 1.0) + x as f32 * CELL_SIZE,
+        board.y + CELL_PAD * (y as f32 + 1.0) + y as f32 * CELL_SIZE,
+    )
+}
+
+// Pure merge step `best_move` searches over: no particles, no board mutation side effects.
+fn try_slide(mut cells: [[Cell; CELL_DIM]; CELL_DIM], dir: Dir) -> ([[Cell; CELL_DIM]; CELL_DIM], u32, bool) {
+    let before = cells;
+    let score = merge(&mut cells, dir, None);
+    let moved = cells != before;
+    (cells, score, moved)
+}
+
+// Shared by `try_slide` and `slide`; `moves`, if given, traces each tile's move for animation.
+fn merge(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], dir: Dir, moves: Option<&mut Vec<TileMove>>) -> u32 {
+    match dir {
+        Dir::Right => merge_right(cells, moves),
+        Dir::Left => merge_left(cells, moves),
+        Dir::Down => merge_down(cells, moves),
+        Dir::Up => merge_up(cells, moves),
+    }
+}
+
+fn merge_right(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], mut moves: Option<&mut Vec<TileMove>>) -> u32 {
     let mut score = 0;
     for y in 0..cells.len() {
         for x in (0..(cells[0].len() - 1)).rev() {
@@ -338,25 +988,30 @@ fn slide_right(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], particles: &mut Vec<Par
                     if cells[y][cell_x + 1] == cells[y][x] && !cells[y][cell_x + 1].combined && !cells[y][x].combined {
                         score += cells[y][x].value * 2;
                         cells[y][cell_x + 1] = Cell { value: cells[y][x].value * 2, combined: true};
-                        let cell_px_x = board.x + CELL_PAD * ((cell_x + 1) as f32 + 1.0) + (cell_x + 1) as f32 * CELL_SIZE;
-                        let cell_px_y = board.y + CELL_PAD * (y as f32 + 1.0) + y as f32 * CELL_SIZE;
-                        particles.append(&mut generate_particles(cell_px_x + CELL_SIZE / 2.0, cell_px_y + CELL_SIZE / 2.0, get_cell_color(cells[y][x].value), 20));
                         cells[y][x] = Cell::empty();
+                        if let Some(moves) = moves.as_deref_mut() {
+                            moves.push(TileMove { from: (x, y), to: (cell_x + 1, y), merged: true });
+                        }
                     }
                     break;
-                } 
+                }
                 cell_x += 1;
             }
             if cell_x != x {
                 cells[y][cell_x] = cells[y][x];
                 cells[y][x] = Cell::empty();
+                if let Some(moves) = moves.as_deref_mut() {
+                    if cells[y][cell_x].is_occupied() {
+                        moves.push(TileMove { from: (x, y), to: (cell_x, y), merged: false });
+                    }
+                }
             }
         }
     }
     score
 }
 
-fn slide_left(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], particles: &mut Vec<Particle>, board: Rectangle) -> u32 {
+fn merge_left(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], mut moves: Option<&mut Vec<TileMove>>) -> u32 {
     let mut score = 0;
     for y in 0..cells.len() {
         for x in 1..cells[0].len() {
@@ -369,10 +1024,10 @@ fn slide_left(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], particles: &mut Vec<Part
                     if cells[y][cell_x - 1] == cells[y][x] && !cells[y][cell_x - 1].combined && !cells[y][x].combined {
                         score += cells[y][x].value * 2;
                         cells[y][cell_x - 1] = Cell { value: cells[y][x].value * 2, combined: true};
-                        let cell_px_x = board.x + CELL_PAD * ((cell_x - 1) as f32 + 1.0) + (cell_x - 1) as f32 * CELL_SIZE;
-                        let cell_px_y = board.y + CELL_PAD * (y as f32 + 1.0) + y as f32 * CELL_SIZE;
-                        particles.append(&mut generate_particles(cell_px_x + CELL_SIZE / 2.0, cell_px_y + CELL_SIZE / 2.0, get_cell_color(cells[y][x].value), 20));
                         cells[y][x] = Cell::empty();
+                        if let Some(moves) = moves.as_deref_mut() {
+                            moves.push(TileMove { from: (x, y), to: (cell_x - 1, y), merged: true });
+                        }
                     }
                     break;
                 }
@@ -381,13 +1036,18 @@ fn slide_left(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], particles: &mut Vec<Part
             if cell_x != x {
                 cells[y][cell_x] = cells[y][x];
                 cells[y][x] = Cell::empty();
+                if let Some(moves) = moves.as_deref_mut() {
+                    if cells[y][cell_x].is_occupied() {
+                        moves.push(TileMove { from: (x, y), to: (cell_x, y), merged: false });
+                    }
+                }
             }
         }
     }
     score
 }
 
-fn slide_down(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], particles: &mut Vec<Particle>, board: Rectangle) -> u32 {
+fn merge_down(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], mut moves: Option<&mut Vec<TileMove>>) -> u32 {
     let mut score = 0;
     for y in (0..(cells.len() - 1)).rev() {
         for x in 0..cells[0].len() {
@@ -400,10 +1060,10 @@ fn slide_down(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], particles: &mut Vec<Part
                     if cells[cell_y + 1][x] == cells[y][x] && !cells[cell_y + 1][x].combined && !cells[y][x].combined {
                         score += cells[y][x].value * 2;
                         cells[cell_y + 1][x] = Cell { value: cells[y][x].value * 2, combined: true};
-                        let cell_px_x = board.x + CELL_PAD * (x as f32 + 1.0) + x as f32 * CELL_SIZE;
-                        let cell_px_y = board.y + CELL_PAD * ((cell_y + 1) as f32 + 1.0) + (cell_y + 1) as f32 * CELL_SIZE;
-                        particles.append(&mut generate_particles(cell_px_x + CELL_SIZE / 2.0, cell_px_y + CELL_SIZE / 2.0, get_cell_color(cells[y][x].value), 20));
                         cells[y][x] = Cell::empty();
+                        if let Some(moves) = moves.as_deref_mut() {
+                            moves.push(TileMove { from: (x, y), to: (x, cell_y + 1), merged: true });
+                        }
                     }
                     break;
                 }
@@ -412,13 +1072,18 @@ fn slide_down(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], particles: &mut Vec<Part
             if cell_y != y {
                 cells[cell_y][x] = cells[y][x];
                 cells[y][x] = Cell::empty();
+                if let Some(moves) = moves.as_deref_mut() {
+                    if cells[cell_y][x].is_occupied() {
+                        moves.push(TileMove { from: (x, y), to: (x, cell_y), merged: false });
+                    }
+                }
             }
         }
     }
     score
 }
 
-fn slide_up(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], particles: &mut Vec<Particle>, board: Rectangle) -> u32 {
+fn merge_up(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], mut moves: Option<&mut Vec<TileMove>>) -> u32 {
     let mut score = 0;
     for y in 1..cells.len() {
         for x in 0..cells[0].len() {
@@ -431,10 +1096,10 @@ fn slide_up(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], particles: &mut Vec<Partic
                     if cells[cell_y - 1][x] == cells[y][x] && !cells[cell_y - 1][x].combined && !cells[y][x].combined {
                         score += cells[y][x].value * 2;
                         cells[cell_y - 1][x] = Cell { value: cells[y][x].value * 2, combined: true};
-                        let cell_px_x = board.x + CELL_PAD * (x as f32 + 1.0) + x as f32 * CELL_SIZE;
-                        let cell_px_y = board.y + CELL_PAD * ((cell_y - 1) as f32 + 1.0) + (cell_y - 1) as f32 * CELL_SIZE;
-                        particles.append(&mut generate_particles(cell_px_x + CELL_SIZE / 2.0, cell_px_y + CELL_SIZE / 2.0, get_cell_color(cells[y][x].value), 20));
                         cells[y][x] = Cell::empty();
+                        if let Some(moves) = moves.as_deref_mut() {
+                            moves.push(TileMove { from: (x, y), to: (x, cell_y - 1), merged: true });
+                        }
                     }
                     break;
                 }
@@ -443,12 +1108,158 @@ fn slide_up(cells: &mut [[Cell; CELL_DIM]; CELL_DIM], particles: &mut Vec<Partic
             if cell_y != y {
                 cells[cell_y][x] = cells[y][x];
                 cells[y][x] = Cell::empty();
+                if let Some(moves) = moves.as_deref_mut() {
+                    if cells[cell_y][x].is_occupied() {
+                        moves.push(TileMove { from: (x, y), to: (x, cell_y), merged: false });
+                    }
+                }
             }
         }
     }
     score
 }
 
+// Expectimax search over `try_slide`; `None` if no move changes the board.
+fn best_move(cells: [[Cell; CELL_DIM]; CELL_DIM]) -> Option<Dir> {
+    let mut best: Option<(Dir, f32)> = None;
+    for dir in Dir::ALL {
+        let (new_cells, gained, moved) = try_slide(cells, dir);
+        if !moved {
+            continue;
+        }
+        let value = gained as f32 + expectimax_chance(new_cells, AI_SEARCH_DEPTH - 1);
+        if best.map_or(true, |(_, best_value)| value > best_value) {
+            best = Some((dir, value));
+        }
+    }
+    best.map(|(dir, _)| dir)
+}
+
+// MAX node: the player picks the direction with the highest expected value.
+fn expectimax_max(cells: [[Cell; CELL_DIM]; CELL_DIM], depth: u32) -> f32 {
+    if depth == 0 {
+        return evaluate_board(&cells);
+    }
+    let mut best: Option<f32> = None;
+    for dir in Dir::ALL {
+        let (new_cells, gained, moved) = try_slide(cells, dir);
+        if !moved {
+            continue;
+        }
+        let value = gained as f32 + expectimax_chance(new_cells, depth - 1);
+        best = Some(best.map_or(value, |b| b.max(value)));
+    }
+    best.unwrap_or_else(|| evaluate_board(&cells))
+}
+
+// CHANCE node: weights a new 2 at 0.9 and a new 4 at 0.1 in each of up to
+// `AI_CHANCE_SAMPLE_LIMIT` randomly chosen empty cells, averaged over those cells.
+fn expectimax_chance(cells: [[Cell; CELL_DIM]; CELL_DIM], depth: u32) -> f32 {
+    if depth == 0 {
+        return evaluate_board(&cells);
+    }
+    let mut empties = Vec::new();
+    for y in 0..CELL_DIM {
+        for x in 0..CELL_DIM {
+            if cells[y][x].is_empty() {
+                empties.push((x, y));
+            }
+        }
+    }
+    if empties.is_empty() {
+        return evaluate_board(&cells);
+    }
+    empties.shuffle(&mut thread_rng());
+    let sample_len = empties.len().min(AI_CHANCE_SAMPLE_LIMIT);
+    let sample = &empties[..sample_len];
+
+    let mut total = 0.0;
+    for &(x, y) in sample {
+        let mut with_two = cells;
+        with_two[y][x] = Cell::occupied(2);
+        let mut with_four = cells;
+        with_four[y][x] = Cell::occupied(4);
+        total += 0.9 * expectimax_max(with_two, depth - 1) + 0.1 * expectimax_max(with_four, depth - 1);
+    }
+    total / sample.len() as f32
+}
+
+// Leaf heuristic: rewards open space, smoothness, monotonicity, and cornering the max tile.
+fn evaluate_board(cells: &[[Cell; CELL_DIM]; CELL_DIM]) -> f32 {
+    let empty = cells.iter().flatten().filter(|cell| cell.is_empty()).count() as f32;
+    EVAL_EMPTY_WEIGHT * empty
+        + EVAL_SMOOTHNESS_WEIGHT * board_smoothness(cells)
+        + EVAL_MONOTONICITY_WEIGHT * board_monotonicity(cells)
+        + EVAL_CORNER_WEIGHT * corner_bonus(cells)
+}
+
+fn board_smoothness(cells: &[[Cell; CELL_DIM]; CELL_DIM]) -> f32 {
+    let mut smoothness = 0.0;
+    for y in 0..CELL_DIM {
+        for x in 0..CELL_DIM {
+            if cells[y][x].is_empty() {
+                continue;
+            }
+            let value = (cells[y][x].value as f32).log2();
+            if x + 1 < CELL_DIM && cells[y][x + 1].is_occupied() {
+                smoothness -= (value - (cells[y][x + 1].value as f32).log2()).abs();
+            }
+            if y + 1 < CELL_DIM && cells[y + 1][x].is_occupied() {
+                smoothness -= (value - (cells[y + 1][x].value as f32).log2()).abs();
+            }
+        }
+    }
+    smoothness
+}
+
+fn board_monotonicity(cells: &[[Cell; CELL_DIM]; CELL_DIM]) -> f32 {
+    let mut totals = [0.0f32; 4]; // increasing/decreasing, rows then columns
+    for y in 0..CELL_DIM {
+        for x in 1..CELL_DIM {
+            let prev = cell_log(cells[y][x - 1]);
+            let cur = cell_log(cells[y][x]);
+            if prev > cur {
+                totals[0] += cur - prev;
+            } else if cur > prev {
+                totals[1] += prev - cur;
+            }
+        }
+    }
+    for x in 0..CELL_DIM {
+        for y in 1..CELL_DIM {
+            let prev = cell_log(cells[y - 1][x]);
+            let cur = cell_log(cells[y][x]);
+            if prev > cur {
+                totals[2] += cur - prev;
+            } else if cur > prev {
+                totals[3] += prev - cur;
+            }
+        }
+    }
+    totals[0].max(totals[1]) + totals[2].max(totals[3])
+}
+
+fn cell_log(cell: Cell) -> f32 {
+    if cell.is_empty() {
+        0.0
+    } else {
+        (cell.value as f32).log2()
+    }
+}
+
+fn corner_bonus(cells: &[[Cell; CELL_DIM]; CELL_DIM]) -> f32 {
+    let max_value = cells.iter().flatten().map(|cell| cell.value).max().unwrap_or(0);
+    if max_value == 0 {
+        return 0.0;
+    }
+    let corners = [(0, 0), (0, CELL_DIM - 1), (CELL_DIM - 1, 0), (CELL_DIM - 1, CELL_DIM - 1)];
+    if corners.iter().any(|&(y, x)| cells[y][x].value == max_value) {
+        1.0
+    } else {
+        0.0
+    }
+}
+
 fn decrease_abs(mut x: f32, amount: f32) -> f32 {
     if x > 0.0 {
         x -= amount;
@@ -466,10 +1277,86 @@ fn decrease_abs(mut x: f32, amount: f32) -> f32 {
     0.0
 }
 
-fn generate_particles(x: f32, y: f32, color: Color, count: u32) -> Vec<Particle> {
-    let mut particles = Vec::new();
-    for _ in 0..count {
-        particles.push(Particle::rand(x, y, color));
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_puzzle(name: &str, contents: &str) -> String {
+        let path = std::env::temp_dir().join(format!("twoohfoureight_test_{}.puzzle", name));
+        fs::write(&path, contents).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn load_board_missing_file_is_io_error() {
+        let result = load_board("/nonexistent/path/to/a/puzzle/file.txt");
+        assert!(matches!(result, Err(LoadError::Io(_))));
     }
-    particles
-}
\ No newline at end of file
+
+    #[test]
+    fn load_board_too_few_lines_is_unexpected_eof() {
+        let path = write_puzzle("eof", "2 2 . .\n4 4 . .\n");
+        assert!(matches!(load_board(&path), Err(LoadError::UnexpectedEof)));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn load_board_wrong_token_count_is_wrong_dimensions() {
+        let path = write_puzzle("dims", "2 2 .\n. . . .\n. . . .\n. . . .\n");
+        assert!(matches!(load_board(&path), Err(LoadError::WrongDimensions)));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn load_board_too_many_tokens_is_too_many_tiles() {
+        let path = write_puzzle("many", "2 2 . . .\n. . . .\n. . . .\n. . . .\n");
+        assert!(matches!(load_board(&path), Err(LoadError::TooManyTiles)));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn load_board_non_power_of_two_is_bad_char() {
+        let path = write_puzzle("badchar", "3 . . .\n. . . .\n. . . .\n. . . .\n");
+        assert!(matches!(load_board(&path), Err(LoadError::BadChar(_))));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn load_board_rejects_one_as_a_tile_value() {
+        let path = write_puzzle("one", "1 . . .\n. . . .\n. . . .\n. . . .\n");
+        assert!(matches!(load_board(&path), Err(LoadError::BadChar(_))));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn load_board_parses_valid_layout_and_target() {
+        let path = write_puzzle("ok", "target 512\n2 4 . .\n. . . .\n. . . .\n. . . .\n");
+        let (cells, target) = load_board(&path).unwrap();
+        assert_eq!(target, Some(512));
+        assert_eq!(cells[0][0].value, 2);
+        assert_eq!(cells[0][1].value, 4);
+        assert_eq!(cells[1][0].value, 0);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn try_slide_merges_equal_tiles_and_scores() {
+        let mut cells = [[Cell::empty(); CELL_DIM]; CELL_DIM];
+        cells[0][0] = Cell::occupied(2);
+        cells[0][1] = Cell::occupied(2);
+        let (after, score, moved) = try_slide(cells, Dir::Right);
+        assert!(moved);
+        assert_eq!(score, 4);
+        assert_eq!(after[0][CELL_DIM - 1].value, 4);
+        assert_eq!(after[0][CELL_DIM - 2].value, 0);
+    }
+
+    #[test]
+    fn try_slide_reports_no_move_when_board_is_unchanged() {
+        let mut cells = [[Cell::empty(); CELL_DIM]; CELL_DIM];
+        cells[0][CELL_DIM - 1] = Cell::occupied(2);
+        let (_, score, moved) = try_slide(cells, Dir::Right);
+        assert!(!moved);
+        assert_eq!(score, 0);
+    }
+}